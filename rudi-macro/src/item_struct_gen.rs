@@ -18,8 +18,17 @@ pub(crate) fn generate(
         binds,
         async_constructor,
         not_auto_register,
+        scope_override,
+        condition,
     } = attribute.simplify();
 
+    let condition = match condition {
+        Some(condition) => quote! { .condition(#condition) },
+        None => quote! {},
+    };
+
+    let scope = scope_override.unwrap_or(scope);
+
     #[cfg(feature = "auto-register")]
     crate::utils::check_auto_register_with_generics(
         not_auto_register,
@@ -106,6 +115,7 @@ pub(crate) fn generate(
                         .name(#name)
                         .eager_create(#eager_create)
                         #binds
+                        #condition
                 )
             }
         }
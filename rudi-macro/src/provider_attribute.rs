@@ -7,7 +7,7 @@ use syn::{
     Expr, ExprPath, Meta, MetaNameValue, Path, Token,
 };
 
-use crate::name::Name;
+use crate::{name::Name, utils::Scope};
 
 pub(crate) struct ProviderAttribute {
     name: Option<(Path, Name)>,
@@ -15,6 +15,8 @@ pub(crate) struct ProviderAttribute {
     binds: Option<(Path, Vec<ExprPath>)>,
     pub(crate) async_constructor: Option<Path>,
     not_auto_register: Option<Path>,
+    scope: Option<(Path, Path)>,
+    condition: Option<(Path, ExprPath)>,
 }
 
 impl Parse for ProviderAttribute {
@@ -24,6 +26,8 @@ impl Parse for ProviderAttribute {
         let mut binds: Option<(Path, Vec<ExprPath>)> = None;
         let mut async_constructor: Option<Path> = None;
         let mut not_auto_register: Option<Path> = None;
+        let mut scope: Option<(Path, Path)> = None;
+        let mut condition: Option<(Path, ExprPath)> = None;
 
         let attr = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
 
@@ -112,9 +116,48 @@ impl Parse for ProviderAttribute {
                 continue;
             }
 
+            if meta_path.is_ident("scope") {
+                check_duplicate!(scope);
+
+                let MetaNameValue { path, value, .. } = require_name_value(meta)?;
+
+                let Expr::Path(scope_path) = value else {
+                    return Err(syn::Error::new(
+                        value.span(),
+                        "the value of `scope` must be an expression path",
+                    ));
+                };
+
+                if !scope_path.path.is_ident("scoped") {
+                    return Err(syn::Error::new(
+                        scope_path.span(),
+                        "the only supported `scope` override is `scoped`",
+                    ));
+                }
+
+                scope = Some((path, scope_path.path));
+                continue;
+            }
+
+            if meta_path.is_ident("condition") {
+                check_duplicate!(condition);
+
+                let MetaNameValue { path, value, .. } = require_name_value(meta)?;
+
+                let Expr::Path(condition_path) = value else {
+                    return Err(syn::Error::new(
+                        value.span(),
+                        "the value of `condition` must be an expression path to a `fn(&Context) -> bool`",
+                    ));
+                };
+
+                condition = Some((path, condition_path));
+                continue;
+            }
+
             return Err(syn::Error::new(
                 meta_path_span,
-                 "the attribute must be one of: `name`, `eager_create`, `binds`, `async_constructor`, `not_auto_register`",
+                 "the attribute must be one of: `name`, `eager_create`, `binds`, `async_constructor`, `not_auto_register`, `scope`, `condition`",
 
             ));
         }
@@ -125,6 +168,8 @@ impl Parse for ProviderAttribute {
             binds,
             async_constructor,
             not_auto_register,
+            scope,
+            condition,
         })
     }
 }
@@ -137,6 +182,8 @@ impl ProviderAttribute {
             binds,
             async_constructor,
             not_auto_register,
+            scope,
+            condition,
         } = self;
 
         SimpleAttribute {
@@ -160,6 +207,8 @@ impl ProviderAttribute {
             },
             async_constructor: async_constructor.is_some(),
             not_auto_register: not_auto_register.is_some(),
+            scope_override: scope.is_some().then_some(Scope::Scoped),
+            condition: condition.as_ref().map(|(_, condition)| quote! { #condition }),
         }
     }
 }
@@ -170,6 +219,8 @@ pub(crate) struct SimpleAttribute {
     pub(crate) binds: TokenStream,
     pub(crate) async_constructor: bool,
     pub(crate) not_auto_register: bool,
+    pub(crate) scope_override: Option<Scope>,
+    pub(crate) condition: Option<TokenStream>,
 }
 
 fn require_path_only(meta: Meta) -> syn::Result<Path> {
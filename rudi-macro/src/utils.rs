@@ -0,0 +1,185 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, Attribute, Generics, Ident, Meta, Token};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Scope {
+    Singleton,
+    Transient,
+    Scoped,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Color {
+    Sync,
+    Async,
+}
+
+pub(crate) fn get_create_provider(scope: Scope, color: Color) -> Ident {
+    let name = match (scope, color) {
+        (Scope::Singleton, Color::Sync) => "singleton",
+        (Scope::Singleton, Color::Async) => "async_singleton",
+        (Scope::Transient, Color::Sync) => "transient",
+        (Scope::Transient, Color::Async) => "async_transient",
+        (Scope::Scoped, Color::Sync) => "scoped",
+        (Scope::Scoped, Color::Async) => "async_scoped",
+    };
+
+    format_ident!("{}", name)
+}
+
+pub(crate) fn check_auto_register_with_generics(
+    not_auto_register: bool,
+    generics: &Generics,
+    kind: &str,
+    _scope: Scope,
+) -> syn::Result<()> {
+    if !not_auto_register && !generics.params.is_empty() {
+        return Err(syn::Error::new(
+            generics.span(),
+            format!(
+                "the `{}` with generics must be marked with `not_auto_register`",
+                kind
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+enum FieldAttribute {
+    None,
+    Name(TokenStream),
+    Collect,
+    Config(proc_macro2::Span, TokenStream),
+    Weak(proc_macro2::Span, TokenStream),
+}
+
+fn parse_field_attribute(attrs: &mut Vec<Attribute>) -> syn::Result<FieldAttribute> {
+    let Some(index) = attrs.iter().position(|attr| attr.path().is_ident("di")) else {
+        return Ok(FieldAttribute::None);
+    };
+
+    let attr = attrs.remove(index);
+
+    if let Ok(lit) = attr.parse_args::<syn::LitStr>() {
+        return Ok(FieldAttribute::Name(quote! { #lit }));
+    }
+
+    let metas = attr.parse_args_with(syn::punctuated::Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+    let mut name: Option<(proc_macro2::Span, syn::Expr)> = None;
+    let mut collect: Option<proc_macro2::Span> = None;
+    let mut config: Option<(proc_macro2::Span, syn::Expr)> = None;
+    let mut weak: Option<proc_macro2::Span> = None;
+
+    for meta in metas {
+        let path = meta.path();
+        let span = path.span();
+
+        if path.is_ident("name") {
+            let Meta::NameValue(name_value) = meta else {
+                return Err(syn::Error::new(span, "`name` must be `name = \"..\"`"));
+            };
+            name = Some((span, name_value.value));
+            continue;
+        }
+
+        if path.is_ident("collect") {
+            meta.require_path_only()?;
+            collect = Some(span);
+            continue;
+        }
+
+        if path.is_ident("config") {
+            let Meta::NameValue(name_value) = meta else {
+                return Err(syn::Error::new(
+                    span,
+                    "`config` must be `config = \"KEY\"`",
+                ));
+            };
+            config = Some((span, name_value.value));
+            continue;
+        }
+
+        if path.is_ident("weak") {
+            meta.require_path_only()?;
+            weak = Some(span);
+            continue;
+        }
+
+        return Err(syn::Error::new(
+            span,
+            "the `di` attribute must be one of: a literal string, `name`, `collect`, `config`, `weak`",
+        ));
+    }
+
+    // `collect` and `config` each pick a wholly different resolution strategy, so
+    // combining either of them with any other key is a conflict, not a priority
+    // order to resolve silently.
+    if let (Some(collect_span), Some(_)) = (collect, &config) {
+        return Err(syn::Error::new(collect_span, "`collect` cannot be combined with `config`"));
+    }
+    if let (Some(collect_span), Some(_)) = (collect, &name) {
+        return Err(syn::Error::new(collect_span, "`collect` cannot be combined with `name`"));
+    }
+    if let (Some(collect_span), Some(_)) = (collect, weak) {
+        return Err(syn::Error::new(collect_span, "`collect` cannot be combined with `weak`"));
+    }
+    if let (Some((config_span, _)), Some(_)) = (&config, &name) {
+        return Err(syn::Error::new(*config_span, "`config` cannot be combined with `name`"));
+    }
+    if let (Some((config_span, _)), Some(_)) = (&config, weak) {
+        return Err(syn::Error::new(*config_span, "`config` cannot be combined with `weak`"));
+    }
+
+    if collect.is_some() {
+        return Ok(FieldAttribute::Collect);
+    }
+
+    if let Some((config_span, config)) = config {
+        return Ok(FieldAttribute::Config(config_span, quote! { #config }));
+    }
+
+    let name = match name {
+        Some((_, name)) => quote! { #name },
+        None => quote! { "" },
+    };
+
+    if let Some(weak_span) = weak {
+        return Ok(FieldAttribute::Weak(weak_span, name));
+    }
+
+    Ok(FieldAttribute::Name(name))
+}
+
+pub(crate) fn get_one_arg_or_field_resolve_expr(
+    attrs: &mut Vec<Attribute>,
+    color: Color,
+) -> syn::Result<TokenStream> {
+    let attribute = parse_field_attribute(attrs)?;
+
+    // `Context` only exposes async-resolving methods for the plain/name/collect
+    // cases; `config`/`weak` have no `_async` counterpart, so combining either
+    // with `async_constructor` is a parse error rather than codegen that can't build.
+    if color == Color::Async {
+        if let FieldAttribute::Config(span, _) = &attribute {
+            return Err(syn::Error::new(*span, "`config` cannot be combined with `async_constructor`: there is no async-resolving `Context` method for it"));
+        }
+        if let FieldAttribute::Weak(span, _) = &attribute {
+            return Err(syn::Error::new(*span, "`weak` cannot be combined with `async_constructor`: there is no async-resolving `Context` method for it"));
+        }
+    }
+
+    Ok(match (attribute, color) {
+        (FieldAttribute::None, Color::Sync) => quote! { cx.resolve() },
+        (FieldAttribute::None, Color::Async) => quote! { cx.resolve_async().await },
+        (FieldAttribute::Name(name), Color::Sync) => quote! { cx.resolve_with_name(#name) },
+        (FieldAttribute::Name(name), Color::Async) => quote! { cx.resolve_with_name_async(#name).await },
+        (FieldAttribute::Collect, Color::Sync) => quote! { cx.resolve_collection() },
+        (FieldAttribute::Collect, Color::Async) => quote! { cx.resolve_collection_async().await },
+        (FieldAttribute::Config(_, key), Color::Sync) => quote! { cx.resolve_config(#key) },
+        (FieldAttribute::Weak(_, name), Color::Sync) => quote! { cx.resolve_weak_with_name(#name) },
+        (FieldAttribute::Config(..), Color::Async) | (FieldAttribute::Weak(..), Color::Async) => unreachable!(),
+    })
+}
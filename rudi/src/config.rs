@@ -0,0 +1,76 @@
+//! Parsing plain strings (env vars, a map, ...) into typed values for `#[di(config = "KEY")]`.
+
+/// Parses a field's target type out of the string handed back by
+/// [`ContextOptions::config_source`](crate::ContextOptions::config_source).
+pub trait FromConfigStr: Sized {
+    fn from_config_str(s: &str) -> Option<Self>;
+}
+
+macro_rules! impl_from_config_str_via_parse {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl FromConfigStr for $ty {
+                fn from_config_str(s: &str) -> Option<Self> {
+                    s.parse().ok()
+                }
+            }
+        )+
+    };
+}
+
+impl_from_config_str_via_parse!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+impl FromConfigStr for bool {
+    fn from_config_str(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Some(true),
+            "false" | "0" | "no" | "off" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl FromConfigStr for String {
+    fn from_config_str(s: &str) -> Option<Self> {
+        Some(s.to_owned())
+    }
+}
+
+/// A Unix timestamp (seconds since the epoch), parsed from either a bare
+/// integer or an RFC 3339-ish `YYYY-MM-DDTHH:MM:SSZ` string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timestamp(pub i64);
+
+impl FromConfigStr for Timestamp {
+    fn from_config_str(s: &str) -> Option<Self> {
+        if let Ok(secs) = s.parse::<i64>() {
+            return Some(Timestamp(secs));
+        }
+
+        let s = s.strip_suffix('Z').unwrap_or(s);
+        let (date, time) = s.split_once('T')?;
+
+        let mut date_parts = date.split('-');
+        let year: i64 = date_parts.next()?.parse().ok()?;
+        let month: i64 = date_parts.next()?.parse().ok()?;
+        let day: i64 = date_parts.next()?.parse().ok()?;
+
+        let mut time_parts = time.split(':');
+        let hour: i64 = time_parts.next()?.parse().ok()?;
+        let minute: i64 = time_parts.next()?.parse().ok()?;
+        let second: i64 = time_parts.next()?.parse().ok()?;
+
+        // Days since the epoch via the days-from-civil algorithm (Howard Hinnant).
+        let y = if month <= 2 { year - 1 } else { year };
+        let era = if y >= 0 { y } else { y - 399 } / 400;
+        let yoe = y - era * 400;
+        let mp = (month + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + day - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = era * 146097 + doe - 719468;
+
+        let secs = days * 86400 + hour * 3600 + minute * 60 + second;
+
+        Some(Timestamp(secs))
+    }
+}
@@ -0,0 +1,440 @@
+use std::{
+    any::{Any, TypeId},
+    borrow::Cow,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use crate::{
+    config::FromConfigStr,
+    error::Error,
+    module::ResolveModule,
+    provider::{key_of, Constructor, DynProvider, Key},
+};
+
+pub(crate) enum IndexEntry {
+    /// The provider registered directly under its own type.
+    Primary(Rc<DynProvider>),
+    /// A type it [`bind`](crate::Provider::bind)s to; `usize` indexes into `binds`.
+    Bound(Rc<DynProvider>, usize),
+}
+
+impl IndexEntry {
+    pub(crate) fn provider(&self) -> &Rc<DynProvider> {
+        match self {
+            IndexEntry::Primary(p) | IndexEntry::Bound(p, _) => p,
+        }
+    }
+}
+
+/// The providers and caches shared by a [`Context`].
+pub(crate) struct Shared {
+    pub(crate) providers: HashMap<Key, IndexEntry>,
+    pub(crate) collections: HashMap<TypeId, Vec<Key>>,
+    pub(crate) singletons: RefCell<HashMap<Key, Rc<dyn Any>>>,
+    pub(crate) config_source: Option<Rc<dyn Fn(&str) -> Option<String>>>,
+    pub(crate) pending_weak: RefCell<HashMap<Key, Box<dyn Any>>>,
+    pub(crate) construction_stack: RefCell<Vec<Key>>,
+}
+
+fn build_shared(providers: Vec<DynProvider>, config_source: Option<Rc<dyn Fn(&str) -> Option<String>>>) -> Shared {
+    let provider_rcs: Vec<Rc<DynProvider>> = providers.into_iter().map(Rc::new).collect();
+
+    // Conditions are evaluated once, against a throwaway `Context` built from
+    // every provider, before the real index (which excludes the losers) exists.
+    let excluded: HashSet<usize> = {
+        let mut preview_providers = HashMap::new();
+        for provider in &provider_rcs {
+            let name = &provider.definition.name;
+            preview_providers.insert((provider.definition.type_id, name.clone()), IndexEntry::Primary(provider.clone()));
+            for (bind_index, bind) in provider.binds.iter().enumerate() {
+                preview_providers.insert((bind.type_id, name.clone()), IndexEntry::Bound(provider.clone(), bind_index));
+            }
+        }
+
+        let preview_shared = Rc::new(Shared {
+            providers: preview_providers,
+            collections: HashMap::new(),
+            singletons: RefCell::new(HashMap::new()),
+            config_source: config_source.clone(),
+            pending_weak: RefCell::new(HashMap::new()),
+            construction_stack: RefCell::new(Vec::new()),
+        });
+        let preview_cx = Context {
+            shared: preview_shared,
+            parent: None,
+            scoped_cache: RefCell::new(HashMap::new()),
+            building_scopes: Rc::new(RefCell::new(Vec::new())),
+        };
+
+        provider_rcs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| match &p.condition {
+                Some(condition) if !condition(&preview_cx) => Some(i),
+                _ => None,
+            })
+            .collect()
+    };
+
+    let mut index = HashMap::new();
+    let mut collections: HashMap<TypeId, Vec<Key>> = HashMap::new();
+
+    for (i, provider) in provider_rcs.into_iter().enumerate() {
+        if excluded.contains(&i) {
+            continue;
+        }
+
+        let definition = &provider.definition;
+        let primary_key: Key = (definition.type_id, definition.name.clone());
+        index.insert(primary_key.clone(), IndexEntry::Primary(provider.clone()));
+        collections.entry(definition.type_id).or_default().push(primary_key);
+
+        for (bind_index, bind) in provider.binds.iter().enumerate() {
+            let key: Key = (bind.type_id, definition.name.clone());
+            index.insert(key.clone(), IndexEntry::Bound(provider.clone(), bind_index));
+            collections.entry(bind.type_id).or_default().push(key);
+        }
+    }
+
+    Shared {
+        providers: index,
+        collections,
+        singletons: RefCell::new(HashMap::new()),
+        config_source,
+        pending_weak: RefCell::new(HashMap::new()),
+        construction_stack: RefCell::new(Vec::new()),
+    }
+}
+
+/// A builder for [`Context`], accepting ad hoc instances and a config source
+/// before the final set of [`Module`](crate::Module)s is registered.
+#[derive(Default)]
+pub struct ContextOptions {
+    instances: Vec<DynProvider>,
+    config_source: Option<Rc<dyn Fn(&str) -> Option<String>>>,
+}
+
+impl ContextOptions {
+    /// Register an already-constructed value as a [`Scope::Singleton`](crate::Scope::Singleton).
+    pub fn instance<T: Clone + 'static>(mut self, value: T) -> Self {
+        self.instances.push(crate::singleton(move |_cx: &mut Context| value.clone()).into());
+        self
+    }
+
+    /// Supply the lookup function backing `#[di(config = "KEY")]` and [`Context::resolve_config`].
+    pub fn config_source<F>(mut self, source: F) -> Self
+    where
+        F: Fn(&str) -> Option<String> + 'static,
+    {
+        self.config_source = Some(Rc::new(source));
+        self
+    }
+
+    pub fn create(self, modules: Vec<ResolveModule>) -> Context {
+        let mut providers = self.instances;
+        for module in modules {
+            providers.extend(module.providers);
+        }
+
+        let mut cx = Context {
+            shared: Rc::new(build_shared(providers, self.config_source)),
+            parent: None,
+            scoped_cache: RefCell::new(HashMap::new()),
+            building_scopes: Rc::new(RefCell::new(Vec::new())),
+        };
+        cx.eager_create_singletons();
+        cx
+    }
+}
+
+/// The dependency injection container: an index of [`Provider`](crate::Provider)s plus
+/// the caches needed to honor each provider's [`Scope`](crate::Scope).
+pub struct Context {
+    pub(crate) shared: Rc<Shared>,
+    pub(crate) parent: Option<Rc<Shared>>,
+    pub(crate) scoped_cache: RefCell<HashMap<Key, Rc<dyn Any>>>,
+    pub(crate) building_scopes: Rc<RefCell<Vec<crate::Scope>>>,
+}
+
+impl Context {
+    /// Start building a [`Context`] with runtime instances.
+    pub fn options() -> ContextOptions {
+        ContextOptions::default()
+    }
+
+    /// Register every provider in `modules` with no runtime instances.
+    pub fn create(modules: Vec<ResolveModule>) -> Context {
+        ContextOptions::default().create(modules)
+    }
+
+    pub(crate) fn has_parent(&self) -> bool {
+        self.parent.is_some()
+    }
+
+    /// A child `Context` with its own [`Scope::Scoped`](crate::Scope::Scoped) cache, falling
+    /// back to this `Context`'s providers and [`Scope::Singleton`](crate::Scope::Singleton)
+    /// cache for anything it doesn't override.
+    pub fn child(&self) -> Context {
+        self.child_with(Vec::new())
+    }
+
+    /// Like [`Context::child`], additionally registering `instances` (which may shadow
+    /// providers from this `Context`) in the child.
+    pub fn child_with(&self, instances: Vec<DynProvider>) -> Context {
+        let mut child = Context {
+            shared: Rc::new(build_shared(instances, self.shared.config_source.clone())),
+            parent: Some(self.shared.clone()),
+            scoped_cache: RefCell::new(HashMap::new()),
+            building_scopes: Rc::new(RefCell::new(Vec::new())),
+        };
+        child.eager_create_singletons();
+        child
+    }
+
+    /// Eagerly construct every `Scope::Singleton` provider marked `eager_create(true)`.
+    fn eager_create_singletons(&mut self) {
+        let keys: Vec<Key> = self
+            .shared
+            .providers
+            .iter()
+            .filter(|(_, entry)| {
+                let definition = &entry.provider().definition;
+                definition.scope == crate::Scope::Singleton && definition.eager_create
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in keys {
+            let Some((owner, provider, _bind_index)) = self.find_entry(&key) else {
+                continue;
+            };
+            let base_key = (provider.definition.type_id, key.1.clone());
+            let _ = self.invoke_sync(&owner, &provider, base_key);
+        }
+    }
+
+    fn find_entry(&self, key: &Key) -> Option<(Rc<Shared>, Rc<DynProvider>, Option<usize>)> {
+        if let Some(entry) = self.shared.providers.get(key) {
+            return Some(match entry {
+                IndexEntry::Primary(p) => (self.shared.clone(), p.clone(), None),
+                IndexEntry::Bound(p, i) => (self.shared.clone(), p.clone(), Some(*i)),
+            });
+        }
+        let parent = self.parent.as_ref()?;
+        let entry = parent.providers.get(key)?;
+        Some(match entry {
+            IndexEntry::Primary(p) => (parent.clone(), p.clone(), None),
+            IndexEntry::Bound(p, i) => (parent.clone(), p.clone(), Some(*i)),
+        })
+    }
+
+    fn invoke_sync(&mut self, owner: &Rc<Shared>, provider: &Rc<DynProvider>, base_key: Key) -> Result<Rc<dyn Any>, Error> {
+        match &provider.constructor {
+            Constructor::Sync(f) => f(self, owner, base_key),
+            Constructor::Async(_) => Err(Error::AsyncConstructor { type_name: provider.definition.type_name }),
+        }
+    }
+
+    async fn invoke_async(&mut self, owner: &Rc<Shared>, provider: &Rc<DynProvider>, base_key: Key) -> Result<Rc<dyn Any>, Error> {
+        match &provider.constructor {
+            Constructor::Async(f) => f(self, owner.clone(), base_key).await,
+            Constructor::Sync(f) => f(self, owner, base_key),
+        }
+    }
+
+    fn finish<T: Clone + 'static>(base: Rc<dyn Any>, provider: &Rc<DynProvider>, bind_index: Option<usize>, key: &Key) -> Result<T, Error> {
+        match bind_index {
+            None => base
+                .downcast::<T>()
+                .map(|rc| (*rc).clone())
+                .map_err(|_| Error::MissingProvider { type_name: std::any::type_name::<T>(), name: key.1.to_string() }),
+            Some(i) => {
+                let boxed = (provider.binds[i].convert)(base);
+                Ok(*boxed.downcast::<T>().expect("bind: type mismatch"))
+            }
+        }
+    }
+
+    /// Resolve the sole provider of `T` registered under the default (empty) name.
+    pub fn resolve<T: Clone + 'static>(&mut self) -> T {
+        self.resolve_with_name("")
+    }
+
+    pub fn try_resolve<T: Clone + 'static>(&mut self) -> Result<T, Error> {
+        self.try_resolve_with_name("")
+    }
+
+    /// Resolve the provider of `T` registered under `name`.
+    pub fn resolve_with_name<T: Clone + 'static>(&mut self, name: impl Into<Cow<'static, str>>) -> T {
+        match self.try_resolve_with_name(name) {
+            Ok(value) => value,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    pub fn try_resolve_with_name<T: Clone + 'static>(&mut self, name: impl Into<Cow<'static, str>>) -> Result<T, Error> {
+        let key = key_of::<T>(&name.into());
+        let (owner, provider, bind_index) = self
+            .find_entry(&key)
+            .ok_or_else(|| Error::MissingProvider { type_name: std::any::type_name::<T>(), name: key.1.to_string() })?;
+        let base_key = (provider.definition.type_id, key.1.clone());
+        let base = self.invoke_sync(&owner, &provider, base_key)?;
+        Self::finish::<T>(base, &provider, bind_index, &key)
+    }
+
+    pub async fn resolve_with_name_async<T: Clone + 'static>(&mut self, name: impl Into<Cow<'static, str>>) -> T {
+        match self.try_resolve_with_name_async(name).await {
+            Ok(value) => value,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    pub async fn try_resolve_with_name_async<T: Clone + 'static>(&mut self, name: impl Into<Cow<'static, str>>) -> Result<T, Error> {
+        let key = key_of::<T>(&name.into());
+        let (owner, provider, bind_index) = self
+            .find_entry(&key)
+            .ok_or_else(|| Error::MissingProvider { type_name: std::any::type_name::<T>(), name: key.1.to_string() })?;
+        let base_key = (provider.definition.type_id, key.1.clone());
+        let base = self.invoke_async(&owner, &provider, base_key).await?;
+        Self::finish::<T>(base, &provider, bind_index, &key)
+    }
+
+    pub async fn resolve_async<T: Clone + 'static>(&mut self) -> T {
+        self.resolve_with_name_async("").await
+    }
+
+    /// Resolve every provider (across every name) registered for `T`, in registration order.
+    pub fn resolve_collection<T: Clone + 'static>(&mut self) -> Vec<T> {
+        match self.try_resolve_collection() {
+            Ok(values) => values,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    pub fn try_resolve_collection<T: Clone + 'static>(&mut self) -> Result<Vec<T>, Error> {
+        let type_id = TypeId::of::<T>();
+        let mut keys = self.shared.collections.get(&type_id).cloned().unwrap_or_default();
+        if let Some(parent) = &self.parent {
+            if let Some(parent_keys) = parent.collections.get(&type_id) {
+                for key in parent_keys {
+                    if !keys.contains(key) {
+                        keys.push(key.clone());
+                    }
+                }
+            }
+        }
+        keys.into_iter().map(|key| self.try_resolve_with_name::<T>(key.1)).collect()
+    }
+
+    pub async fn resolve_collection_async<T: Clone + 'static>(&mut self) -> Vec<T> {
+        let type_id = TypeId::of::<T>();
+        let mut keys = self.shared.collections.get(&type_id).cloned().unwrap_or_default();
+        if let Some(parent) = &self.parent {
+            if let Some(parent_keys) = parent.collections.get(&type_id) {
+                for key in parent_keys {
+                    if !keys.contains(key) {
+                        keys.push(key.clone());
+                    }
+                }
+            }
+        }
+        let mut out = Vec::with_capacity(keys.len());
+        for key in keys {
+            out.push(self.resolve_with_name_async(key.1).await);
+        }
+        out
+    }
+
+    /// Look up the [`DynProvider`] registered for `T` under the default name, if any.
+    pub fn get_provider<T: 'static>(&self) -> Option<&DynProvider> {
+        let key = key_of::<T>("");
+        self.shared
+            .providers
+            .get(&key)
+            .or_else(|| self.parent.as_ref().and_then(|p| p.providers.get(&key)))
+            .map(|entry| entry.provider().as_ref())
+    }
+
+    /// The number of singletons constructed (and cached) by this `Context` so far.
+    pub fn singletons_len(&self) -> usize {
+        self.shared.singletons.borrow().len()
+    }
+
+    /// Iterate over the providers backing every singleton constructed so far.
+    pub fn iter(&self) -> impl Iterator<Item = ((TypeId, Cow<'static, str>), Rc<DynProvider>)> {
+        let providers = &self.shared.providers;
+        self.shared
+            .singletons
+            .borrow()
+            .keys()
+            .filter_map(|key| providers.get(key).map(|entry| (key.clone(), entry.provider().clone())))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Read a value for `key` out of [`ContextOptions::config_source`] and parse it as `T`.
+    pub fn resolve_config<T: FromConfigStr + 'static>(&mut self, key: &str) -> T {
+        match self.try_resolve_config(key) {
+            Ok(value) => value,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    pub fn try_resolve_config<T: FromConfigStr + 'static>(&mut self, key: &str) -> Result<T, Error> {
+        let source = self
+            .shared
+            .config_source
+            .clone()
+            .or_else(|| self.parent.as_ref().and_then(|p| p.config_source.clone()));
+
+        let raw = source
+            .as_ref()
+            .and_then(|f| f(key))
+            .ok_or_else(|| Error::MissingConfig { key: key.to_owned(), type_name: std::any::type_name::<T>() })?;
+
+        T::from_config_str(&raw).ok_or_else(|| Error::BadConfig {
+            key: key.to_owned(),
+            type_name: std::any::type_name::<T>(),
+            value: raw,
+        })
+    }
+
+    /// Resolve a [`std::rc::Weak`] handle to a [`Scope::Singleton`](crate::Scope::Singleton)
+    /// provider, without forcing its construction if it's already mid-construction
+    /// higher up the stack (i.e. this is the `#[di(weak)]` edge breaking a cycle).
+    pub fn resolve_weak_with_name<T: 'static>(&mut self, name: impl Into<Cow<'static, str>>) -> std::rc::Weak<T> {
+        match self.try_resolve_weak_with_name(name) {
+            Ok(weak) => weak,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    pub fn try_resolve_weak_with_name<T: 'static>(&mut self, name: impl Into<Cow<'static, str>>) -> Result<std::rc::Weak<T>, Error> {
+        let key = key_of::<T>(&name.into());
+        let (owner, provider, _bind_index) = self
+            .find_entry(&key)
+            .ok_or_else(|| Error::MissingProvider { type_name: std::any::type_name::<T>(), name: key.1.to_string() })?;
+
+        if provider.definition.scope != crate::Scope::Singleton {
+            return Err(Error::WeakTargetNotSingleton { type_name: std::any::type_name::<T>() });
+        }
+
+        let base_key = (provider.definition.type_id, key.1.clone());
+
+        if let Some(pending) = owner.pending_weak.borrow().get(&base_key) {
+            let weak = pending
+                .downcast_ref::<std::rc::Weak<T>>()
+                .expect("weak handle type mismatch")
+                .clone();
+            return Ok(weak);
+        }
+
+        let base = self.invoke_sync(&owner, &provider, base_key)?;
+        let rc = base
+            .downcast::<T>()
+            .map_err(|_| Error::MissingProvider { type_name: std::any::type_name::<T>(), name: key.1.to_string() })?;
+        Ok(Rc::downgrade(&rc))
+    }
+}
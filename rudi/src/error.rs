@@ -0,0 +1,68 @@
+use std::fmt;
+
+/// Errors produced while resolving providers out of a [`Context`](crate::Context).
+#[derive(Debug)]
+pub enum Error {
+    /// No provider was registered for the requested type/name.
+    MissingProvider { type_name: &'static str, name: String },
+    /// A [`Scope::Scoped`](crate::Scope::Scoped) provider was resolved directly from a
+    /// root [`Context`](crate::Context) instead of one of its [`child`](crate::Context::child)ren.
+    ScopedOutsideChild { type_name: &'static str },
+    /// A [`Scope::Scoped`](crate::Scope::Scoped) provider was resolved while a
+    /// [`Scope::Singleton`](crate::Scope::Singleton) was still under construction, which
+    /// would let the scoped value outlive the child it's cached in.
+    ScopedOutlivesSingleton { type_name: &'static str },
+    /// No [`ContextOptions::config_source`](crate::ContextOptions::config_source) was set,
+    /// or it had nothing for `key`.
+    MissingConfig { key: String, type_name: &'static str },
+    /// A config value for `key` was found but didn't parse as `type_name`.
+    BadConfig { key: String, type_name: &'static str, value: String },
+    /// Resolving a [`Scope::Singleton`](crate::Scope::Singleton) re-entered its own
+    /// construction through only strong (non-`#[di(weak)]`) edges.
+    Cycle { chain: Vec<String> },
+    /// A provider with an async constructor was resolved with a synchronous
+    /// `resolve`/`try_resolve` method; use the matching `_async` method instead.
+    AsyncConstructor { type_name: &'static str },
+    /// [`Context::resolve_weak_with_name`](crate::Context::resolve_weak_with_name) was
+    /// pointed at a provider that isn't [`Scope::Singleton`](crate::Scope::Singleton) — a
+    /// `Weak` to a [`Scope::Transient`](crate::Scope::Transient) or
+    /// [`Scope::Scoped`](crate::Scope::Scoped) value would dangle the instant it's returned,
+    /// since nothing keeps the freshly built value alive.
+    WeakTargetNotSingleton { type_name: &'static str },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingProvider { type_name, name } => {
+                write!(f, "no provider registered for `{type_name}` (name = {name:?})")
+            }
+            Error::ScopedOutsideChild { type_name } => {
+                write!(f, "`{type_name}` is `Scope::Scoped` and can only be resolved from `Context::child`")
+            }
+            Error::ScopedOutlivesSingleton { type_name } => {
+                write!(
+                    f,
+                    "`{type_name}` is `Scope::Scoped` and cannot be resolved while a `Scope::Singleton` is under construction"
+                )
+            }
+            Error::MissingConfig { key, type_name } => {
+                write!(f, "no config value found for key {key:?} (expected `{type_name}`)")
+            }
+            Error::BadConfig { key, type_name, value } => {
+                write!(f, "config value {value:?} for key {key:?} does not parse as `{type_name}`")
+            }
+            Error::Cycle { chain } => {
+                write!(f, "cycle detected with only strong edges: {}", chain.join(" -> "))
+            }
+            Error::AsyncConstructor { type_name } => {
+                write!(f, "`{type_name}` has an async constructor; resolve it with an `_async` method instead")
+            }
+            Error::WeakTargetNotSingleton { type_name } => {
+                write!(f, "`{type_name}` is not `Scope::Singleton`; `#[di(weak)]` can only target a singleton")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,20 @@
+//! A lightweight, single-threaded dependency injection container.
+
+mod config;
+mod context;
+mod error;
+mod macros;
+mod module;
+mod provider;
+mod scope;
+
+pub use config::{FromConfigStr, Timestamp};
+pub use context::{Context, ContextOptions};
+pub use error::Error;
+pub use module::{Module, ResolveModule};
+pub use rudi_macro::{Singleton, Transient};
+pub use provider::{
+    async_scoped, async_singleton, async_transient, scoped, singleton, transient, DefaultProvider, DynProvider,
+    Provider, ProviderDefinition,
+};
+pub use scope::Scope;
@@ -0,0 +1,19 @@
+use crate::DynProvider;
+
+/// A group of providers that can be registered together via [`modules!`](crate::modules).
+pub trait Module {
+    fn providers() -> Vec<DynProvider>;
+}
+
+/// A type-erased, resolved [`Module`].
+pub struct ResolveModule {
+    pub(crate) providers: Vec<DynProvider>,
+}
+
+impl ResolveModule {
+    pub fn new<M: Module>() -> Self {
+        ResolveModule {
+            providers: M::providers(),
+        }
+    }
+}
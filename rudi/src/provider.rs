@@ -0,0 +1,352 @@
+use std::{
+    any::{Any, TypeId},
+    borrow::Cow,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    rc::Rc,
+};
+
+use crate::{context::Shared, error::Error, scope::Scope, Context};
+
+pub(crate) type Key = (TypeId, Cow<'static, str>);
+
+pub(crate) fn key_of<T: 'static>(name: &str) -> Key {
+    (TypeId::of::<T>(), Cow::Owned(name.to_owned()))
+}
+
+type SyncConstructor = Rc<dyn Fn(&mut Context, &Rc<Shared>, Key) -> Result<Rc<dyn Any>, Error>>;
+type AsyncConstructor =
+    Rc<dyn Fn(&mut Context, Rc<Shared>, Key) -> Pin<Box<dyn Future<Output = Result<Rc<dyn Any>, Error>>>>>;
+
+pub(crate) enum Constructor {
+    Sync(SyncConstructor),
+    Async(AsyncConstructor),
+}
+
+pub(crate) struct BindEntry {
+    pub(crate) type_id: TypeId,
+    pub(crate) type_name: &'static str,
+    pub(crate) convert: Rc<dyn Fn(Rc<dyn Any>) -> Box<dyn Any>>,
+}
+
+/// The static metadata of a registered provider.
+#[derive(Clone)]
+pub struct ProviderDefinition {
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+    pub name: Cow<'static, str>,
+    pub scope: Scope,
+    pub eager_create: bool,
+}
+
+/// A type-erased, registered provider.
+///
+/// Created from a [`Provider<T>`] via [`From`]; stored in a [`Context`]'s index.
+pub struct DynProvider {
+    pub(crate) definition: ProviderDefinition,
+    pub(crate) constructor: Constructor,
+    pub(crate) binds: Vec<BindEntry>,
+    pub(crate) condition: Option<Rc<dyn Fn(&Context) -> bool>>,
+}
+
+impl DynProvider {
+    pub fn definition(&self) -> &ProviderDefinition {
+        &self.definition
+    }
+
+    pub fn eager_create(&self) -> bool {
+        self.definition.eager_create
+    }
+}
+
+/// A builder for a single provider of type `T`, produced by `#[Singleton]`/`#[Transient]`
+/// or the [`singleton`]/[`transient`] family of functions.
+pub struct Provider<T> {
+    definition: ProviderDefinition,
+    constructor: Constructor,
+    binds: Vec<BindEntry>,
+    condition: Option<Rc<dyn Fn(&Context) -> bool>>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: 'static> Provider<T> {
+    fn new(scope: Scope, constructor: Constructor) -> Self {
+        Provider {
+            definition: ProviderDefinition {
+                type_id: TypeId::of::<T>(),
+                type_name: std::any::type_name::<T>(),
+                name: Cow::Borrowed(""),
+                scope,
+                eager_create: false,
+            },
+            constructor,
+            binds: Vec::new(),
+            condition: None,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn name(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.definition.name = name.into();
+        self
+    }
+
+    pub fn eager_create(mut self, eager_create: bool) -> Self {
+        self.definition.eager_create = eager_create;
+        self
+    }
+
+    /// Only register this provider if `condition` returns `true`, evaluated once
+    /// against a preview `Context` built from every provider before the real one exists.
+    pub fn condition(mut self, condition: fn(&Context) -> bool) -> Self {
+        self.condition = Some(Rc::new(condition));
+        self
+    }
+
+    /// Also register the output of `f` under its own type, sharing this
+    /// provider's scope and (for [`Scope::Singleton`]) cache slot.
+    pub fn bind<R: 'static>(mut self, f: fn(T) -> R) -> Self
+    where
+        T: Clone,
+    {
+        self.binds.push(BindEntry {
+            type_id: TypeId::of::<R>(),
+            type_name: std::any::type_name::<R>(),
+            convert: Rc::new(move |base: Rc<dyn Any>| {
+                let base = base.downcast::<T>().expect("bind: type mismatch");
+                Box::new(f((*base).clone())) as Box<dyn Any>
+            }),
+        });
+        self
+    }
+}
+
+impl<T: 'static> From<Provider<T>> for DynProvider {
+    fn from(provider: Provider<T>) -> Self {
+        DynProvider {
+            definition: provider.definition,
+            constructor: provider.constructor,
+            binds: provider.binds,
+            condition: provider.condition,
+        }
+    }
+}
+
+/// Implemented by `#[Singleton]`/`#[Transient]`-annotated types to produce their own [`Provider`].
+pub trait DefaultProvider {
+    type Type;
+
+    fn provider() -> Provider<Self::Type>;
+}
+
+fn cycle_error(owner: &Shared, closing_key: &Key) -> Error {
+    let type_name_of = |k: &Key| {
+        owner
+            .providers
+            .get(k)
+            .map(|entry| entry.provider().definition.type_name.to_string())
+            .unwrap_or_else(|| "<unknown>".to_string())
+    };
+
+    let mut chain: Vec<String> = owner.construction_stack.borrow().iter().map(type_name_of).collect();
+    chain.push(type_name_of(closing_key));
+
+    Error::Cycle { chain }
+}
+
+/// Constructs `T` via [`Rc::new_cyclic`] so that a sibling resolving the same
+/// key as [`Context::resolve_weak_with_name`] while `f` is still running can
+/// retrieve a valid (if not-yet-upgradeable) [`std::rc::Weak`] handle to it.
+fn build_singleton<T, F>(cx: &mut Context, owner: &Rc<Shared>, key: Key, f: &F) -> Result<Rc<dyn Any>, Error>
+where
+    T: 'static,
+    F: Fn(&mut Context) -> T,
+{
+    if let Some(cached) = owner.singletons.borrow().get(&key) {
+        return Ok(cached.clone());
+    }
+
+    if owner.construction_stack.borrow().contains(&key) {
+        return Err(cycle_error(owner, &key));
+    }
+
+    owner.construction_stack.borrow_mut().push(key.clone());
+    cx.building_scopes.borrow_mut().push(Scope::Singleton);
+
+    let rc: Rc<T> = Rc::new_cyclic(|weak_self| {
+        owner.pending_weak.borrow_mut().insert(key.clone(), Box::new(weak_self.clone()));
+        f(cx)
+    });
+
+    cx.building_scopes.borrow_mut().pop();
+    owner.construction_stack.borrow_mut().pop();
+    owner.pending_weak.borrow_mut().remove(&key);
+
+    let rc_any: Rc<dyn Any> = rc;
+    owner.singletons.borrow_mut().insert(key, rc_any.clone());
+    Ok(rc_any)
+}
+
+async fn build_async_singleton<T, F, Fut>(cx: &mut Context, owner: Rc<Shared>, key: Key, f: Rc<F>) -> Result<Rc<dyn Any>, Error>
+where
+    T: 'static,
+    F: Fn(&mut Context) -> Fut,
+    Fut: Future<Output = T>,
+{
+    if let Some(cached) = owner.singletons.borrow().get(&key) {
+        return Ok(cached.clone());
+    }
+
+    if owner.construction_stack.borrow().contains(&key) {
+        return Err(cycle_error(&owner, &key));
+    }
+
+    owner.construction_stack.borrow_mut().push(key.clone());
+    cx.building_scopes.borrow_mut().push(Scope::Singleton);
+
+    let value = f(cx).await;
+
+    cx.building_scopes.borrow_mut().pop();
+    owner.construction_stack.borrow_mut().pop();
+
+    let rc_any: Rc<dyn Any> = Rc::new(value);
+    owner.singletons.borrow_mut().insert(key, rc_any.clone());
+    Ok(rc_any)
+}
+
+fn build_scoped<T, F>(cx: &mut Context, key: Key, f: &F) -> Result<Rc<dyn Any>, Error>
+where
+    T: 'static,
+    F: Fn(&mut Context) -> T,
+{
+    if !cx.building_scopes.borrow().is_empty() {
+        return Err(Error::ScopedOutlivesSingleton { type_name: std::any::type_name::<T>() });
+    }
+    if !cx.has_parent() {
+        return Err(Error::ScopedOutsideChild { type_name: std::any::type_name::<T>() });
+    }
+
+    if let Some(cached) = cx.scoped_cache.borrow().get(&key) {
+        return Ok(cached.clone());
+    }
+
+    let rc_any: Rc<dyn Any> = Rc::new(f(cx));
+    cx.scoped_cache.borrow_mut().insert(key, rc_any.clone());
+    Ok(rc_any)
+}
+
+async fn build_async_scoped<T, F, Fut>(cx: &mut Context, key: Key, f: Rc<F>) -> Result<Rc<dyn Any>, Error>
+where
+    T: 'static,
+    F: Fn(&mut Context) -> Fut,
+    Fut: Future<Output = T>,
+{
+    if !cx.building_scopes.borrow().is_empty() {
+        return Err(Error::ScopedOutlivesSingleton { type_name: std::any::type_name::<T>() });
+    }
+    if !cx.has_parent() {
+        return Err(Error::ScopedOutsideChild { type_name: std::any::type_name::<T>() });
+    }
+
+    if let Some(cached) = cx.scoped_cache.borrow().get(&key) {
+        return Ok(cached.clone());
+    }
+
+    let rc_any: Rc<dyn Any> = Rc::new(f(cx).await);
+    cx.scoped_cache.borrow_mut().insert(key, rc_any.clone());
+    Ok(rc_any)
+}
+
+/// A [`Scope::Singleton`] provider, constructed once per [`Context`] and cached.
+pub fn singleton<T, F>(f: F) -> Provider<T>
+where
+    T: 'static,
+    F: Fn(&mut Context) -> T + 'static,
+{
+    Provider::new(
+        Scope::Singleton,
+        Constructor::Sync(Rc::new(move |cx, owner, key| build_singleton(cx, owner, key, &f))),
+    )
+}
+
+/// A [`Scope::Transient`] provider, constructed fresh on every resolution.
+pub fn transient<T, F>(f: F) -> Provider<T>
+where
+    T: 'static,
+    F: Fn(&mut Context) -> T + 'static,
+{
+    Provider::new(
+        Scope::Transient,
+        Constructor::Sync(Rc::new(move |cx, _owner, _key| Ok(Rc::new(f(cx)) as Rc<dyn Any>))),
+    )
+}
+
+/// A [`Scope::Scoped`] provider, constructed once per [`Context::child`] and dropped with it.
+///
+/// Can only be resolved from a child `Context`, and never while a
+/// [`Scope::Singleton`] is under construction (which would let the scoped value
+/// outlive the child it's cached in).
+pub fn scoped<T, F>(f: F) -> Provider<T>
+where
+    T: 'static,
+    F: Fn(&mut Context) -> T + 'static,
+{
+    Provider::new(
+        Scope::Scoped,
+        Constructor::Sync(Rc::new(move |cx, _owner, key| build_scoped(cx, key, &f))),
+    )
+}
+
+/// A [`Scope::Singleton`] provider with an async constructor.
+pub fn async_singleton<T, F, Fut>(f: F) -> Provider<T>
+where
+    T: 'static,
+    F: Fn(&mut Context) -> Fut + 'static,
+    Fut: Future<Output = T> + 'static,
+{
+    let f = Rc::new(f);
+    Provider::new(
+        Scope::Singleton,
+        Constructor::Async(Rc::new(move |cx, owner, key| {
+            let f = f.clone();
+            Box::pin(async move { build_async_singleton(cx, owner, key, f).await })
+        })),
+    )
+}
+
+/// A [`Scope::Transient`] provider with an async constructor, constructed fresh
+/// on every resolution.
+pub fn async_transient<T, F, Fut>(f: F) -> Provider<T>
+where
+    T: 'static,
+    F: Fn(&mut Context) -> Fut + 'static,
+    Fut: Future<Output = T> + 'static,
+{
+    let f = Rc::new(f);
+    Provider::new(
+        Scope::Transient,
+        Constructor::Async(Rc::new(move |cx, _owner, _key| {
+            let f = f.clone();
+            Box::pin(async move { Ok(Rc::new(f(cx).await) as Rc<dyn Any>) })
+        })),
+    )
+}
+
+/// A [`Scope::Scoped`] provider with an async constructor, constructed once per
+/// [`Context::child`] and dropped with it.
+pub fn async_scoped<T, F, Fut>(f: F) -> Provider<T>
+where
+    T: 'static,
+    F: Fn(&mut Context) -> Fut + 'static,
+    Fut: Future<Output = T> + 'static,
+{
+    let f = Rc::new(f);
+    Provider::new(
+        Scope::Scoped,
+        Constructor::Async(Rc::new(move |cx, _owner, key| {
+            let f = f.clone();
+            Box::pin(async move { build_async_scoped(cx, key, f).await })
+        })),
+    )
+}
@@ -0,0 +1,10 @@
+/// The lifetime a [`Provider`](crate::Provider) is resolved with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// Constructed once per [`Context`](crate::Context) and cached.
+    Singleton,
+    /// Constructed fresh on every `resolve`.
+    Transient,
+    /// Constructed once per child [`Context`](crate::Context::child) and dropped with it.
+    Scoped,
+}
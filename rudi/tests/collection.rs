@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use rudi::{modules, Context, Module, Singleton};
+
+trait Plugin: Send + Sync {
+    fn name(&self) -> &str;
+}
+
+#[derive(Clone)]
+#[Singleton(name = "a", binds = [Self::into_plugin])]
+struct PluginA;
+
+impl PluginA {
+    fn into_plugin(self) -> Arc<dyn Plugin> {
+        Arc::new(self)
+    }
+}
+
+impl Plugin for PluginA {
+    fn name(&self) -> &str {
+        "a"
+    }
+}
+
+#[derive(Clone)]
+#[Singleton(name = "b", binds = [Self::into_plugin])]
+struct PluginB;
+
+impl PluginB {
+    fn into_plugin(self) -> Arc<dyn Plugin> {
+        Arc::new(self)
+    }
+}
+
+impl Plugin for PluginB {
+    fn name(&self) -> &str {
+        "b"
+    }
+}
+
+#[derive(Clone)]
+#[Singleton]
+struct Registry {
+    #[di(collect)]
+    plugins: Vec<Arc<dyn Plugin>>,
+}
+
+struct MyModule;
+impl Module for MyModule {
+    fn providers() -> Vec<rudi::DynProvider> {
+        rudi::components![PluginA, PluginB, Registry]
+    }
+}
+
+#[test]
+fn collects_all_bindings_for_a_type() {
+    let mut cx = Context::create(modules![MyModule]);
+
+    let registry = cx.resolve::<Registry>();
+    let names: Vec<_> = registry.plugins.iter().map(|p| p.name()).collect();
+
+    assert_eq!(names, vec!["a", "b"]);
+}
+
+#[test]
+fn empty_collection_is_not_a_panic() {
+    struct EmptyModule;
+    impl Module for EmptyModule {
+        fn providers() -> Vec<rudi::DynProvider> {
+            Vec::new()
+        }
+    }
+
+    let mut cx = Context::create(modules![EmptyModule]);
+
+    assert!(cx.resolve_collection::<Arc<dyn Plugin>>().is_empty());
+}
@@ -0,0 +1,32 @@
+use rudi::{modules, Context, Module, Singleton};
+
+fn always_enabled(_cx: &Context) -> bool {
+    true
+}
+
+fn always_disabled(_cx: &Context) -> bool {
+    false
+}
+
+#[derive(Clone)]
+#[Singleton(condition = always_enabled)]
+struct Enabled;
+
+#[derive(Clone)]
+#[Singleton(condition = always_disabled)]
+struct Disabled;
+
+struct MyModule;
+impl Module for MyModule {
+    fn providers() -> Vec<rudi::DynProvider> {
+        rudi::components![Enabled, Disabled]
+    }
+}
+
+#[test]
+fn condition_gates_registration() {
+    let cx = Context::create(modules![MyModule]);
+
+    assert!(cx.get_provider::<Enabled>().is_some());
+    assert!(cx.get_provider::<Disabled>().is_none());
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+use rudi::{modules, Context, Module, Singleton, Timestamp};
+
+#[derive(Clone)]
+#[Singleton]
+struct Settings {
+    #[di(config = "NUM")]
+    num: i32,
+    #[di(config = "SUCCESS")]
+    success: bool,
+}
+
+struct MyModule;
+impl Module for MyModule {
+    fn providers() -> Vec<rudi::DynProvider> {
+        rudi::components![Settings]
+    }
+}
+
+#[test]
+fn config_values_are_parsed_from_the_config_source() {
+    let mut source = HashMap::new();
+    source.insert("NUM".to_string(), "42".to_string());
+    source.insert("SUCCESS".to_string(), "true".to_string());
+
+    let mut cx = Context::options()
+        .config_source(move |key| source.get(key).cloned())
+        .create(modules![MyModule]);
+
+    let settings = cx.resolve::<Settings>();
+
+    assert_eq!(settings.num, 42);
+    assert!(settings.success);
+}
+
+#[derive(Clone)]
+#[Singleton]
+struct Scheduled {
+    #[di(config = "RUNS_AT")]
+    runs_at: Timestamp,
+}
+
+struct TimestampModule;
+impl Module for TimestampModule {
+    fn providers() -> Vec<rudi::DynProvider> {
+        rudi::components![Scheduled]
+    }
+}
+
+#[test]
+fn a_bare_integer_config_value_parses_as_a_timestamp() {
+    let mut source = HashMap::new();
+    source.insert("RUNS_AT".to_string(), "1700000000".to_string());
+
+    let mut cx = Context::options()
+        .config_source(move |key| source.get(key).cloned())
+        .create(modules![TimestampModule]);
+
+    assert_eq!(cx.resolve::<Scheduled>().runs_at, Timestamp(1700000000));
+}
+
+#[test]
+fn an_rfc_3339_ish_config_value_parses_as_a_timestamp() {
+    let mut source = HashMap::new();
+    source.insert("RUNS_AT".to_string(), "2023-11-14T22:13:20Z".to_string());
+
+    let mut cx = Context::options()
+        .config_source(move |key| source.get(key).cloned())
+        .create(modules![TimestampModule]);
+
+    assert_eq!(cx.resolve::<Scheduled>().runs_at, Timestamp(1700000000));
+}
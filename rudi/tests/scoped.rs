@@ -0,0 +1,75 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use rudi::{modules, scoped, Context, DynProvider, Module};
+
+static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Clone)]
+struct RequestState {
+    id: u32,
+}
+
+struct MyModule;
+impl Module for MyModule {
+    fn providers() -> Vec<DynProvider> {
+        rudi::providers![scoped(|_cx| RequestState {
+            id: NEXT_ID.fetch_add(1, Ordering::SeqCst),
+        })]
+    }
+}
+
+#[test]
+fn scoped_provider_is_cached_per_child() {
+    let cx = Context::create(modules![MyModule]);
+
+    let mut child_a = cx.child();
+    let a1 = child_a.resolve::<RequestState>();
+    let a2 = child_a.resolve::<RequestState>();
+    assert_eq!(a1.id, a2.id);
+
+    let mut child_b = cx.child();
+    let b1 = child_b.resolve::<RequestState>();
+    assert_ne!(a1.id, b1.id);
+}
+
+#[test]
+#[should_panic(expected = "can only be resolved from `Context::child`")]
+fn scoped_provider_cannot_be_resolved_outside_a_child() {
+    let mut cx = Context::create(modules![MyModule]);
+    cx.resolve::<RequestState>();
+}
+
+#[derive(Clone)]
+struct Holder;
+
+struct OutlivesModule;
+impl Module for OutlivesModule {
+    fn providers() -> Vec<DynProvider> {
+        rudi::providers![
+            scoped(|_cx| RequestState {
+                id: NEXT_ID.fetch_add(1, Ordering::SeqCst),
+            }),
+            rudi::singleton(|cx| {
+                cx.resolve::<RequestState>();
+                Holder
+            }),
+        ]
+    }
+}
+
+#[test]
+#[should_panic(expected = "cannot be resolved while a `Scope::Singleton` is under construction")]
+fn scoped_cannot_be_resolved_while_a_singleton_is_under_construction() {
+    let cx = Context::create(modules![OutlivesModule]);
+    let mut child = cx.child();
+    child.resolve::<Holder>();
+}
+
+#[test]
+fn child_with_instances_shadow_the_parents_provider() {
+    let cx = Context::create(modules![MyModule]);
+
+    let mut child = cx.child_with(rudi::providers![scoped(|_cx| RequestState { id: 999 })]);
+
+    assert_eq!(child.resolve::<RequestState>().id, 999);
+}
@@ -0,0 +1,89 @@
+use std::rc::Weak;
+
+use rudi::{modules, Context, Module, Singleton};
+
+#[derive(Clone)]
+#[Singleton]
+struct A {
+    #[di(weak)]
+    b: Weak<B>,
+}
+
+#[derive(Clone)]
+#[Singleton]
+struct B {
+    a: A,
+}
+
+struct MyModule;
+impl Module for MyModule {
+    fn providers() -> Vec<rudi::DynProvider> {
+        rudi::components![A, B]
+    }
+}
+
+#[test]
+fn weak_edge_breaks_the_cycle() {
+    let mut cx = Context::create(modules![MyModule]);
+
+    let b = cx.resolve::<B>();
+
+    assert!(b.a.b.upgrade().is_some());
+}
+
+#[derive(Clone)]
+#[Singleton(binds = [Self::into_rc])]
+struct C {
+    d: std::rc::Rc<D>,
+}
+
+impl C {
+    fn into_rc(self) -> std::rc::Rc<C> {
+        std::rc::Rc::new(self)
+    }
+}
+
+#[derive(Clone)]
+#[Singleton(binds = [Self::into_rc])]
+struct D {
+    c: std::rc::Rc<C>,
+}
+
+impl D {
+    fn into_rc(self) -> std::rc::Rc<D> {
+        std::rc::Rc::new(self)
+    }
+}
+
+struct CyclicModule;
+impl Module for CyclicModule {
+    fn providers() -> Vec<rudi::DynProvider> {
+        rudi::components![C, D]
+    }
+}
+
+#[test]
+#[should_panic(expected = "cycle detected with only strong edges")]
+fn an_all_strong_cycle_is_rejected() {
+    let mut cx = Context::create(modules![CyclicModule]);
+
+    cx.resolve::<C>();
+}
+
+struct Ephemeral;
+
+struct EphemeralModule;
+impl Module for EphemeralModule {
+    fn providers() -> Vec<rudi::DynProvider> {
+        vec![rudi::transient(|_cx| Ephemeral).into()]
+    }
+}
+
+#[test]
+fn resolving_a_weak_handle_to_a_transient_is_rejected() {
+    let mut cx = Context::create(modules![EphemeralModule]);
+
+    let err = cx.try_resolve_weak_with_name::<Ephemeral>("").unwrap_err();
+
+    assert!(matches!(err, rudi::Error::WeakTargetNotSingleton { .. }));
+}